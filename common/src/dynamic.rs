@@ -0,0 +1,62 @@
+//! OpenSBI `fw_dynamic` 信息结构。
+//!
+//! 前一阶段以 a0 = hartid，a1 = 设备树地址，a2 = [`DynamicInfo`] 地址跳转到下一阶段。
+
+use crate::memory::{DRAM, SRAM};
+
+/// `"OSBI"`
+pub const MAGIC: usize = 0x4942_534f;
+pub const VERSION: usize = 2;
+/// 支持的最低版本。
+pub const VERSION_MIN: usize = 1;
+
+pub const NEXT_MODE_U: usize = 0;
+pub const NEXT_MODE_S: usize = 1;
+pub const NEXT_MODE_M: usize = 3;
+
+/// 下一阶段不打印启动信息。
+pub const OPTION_NO_BOOT_PRINTS: usize = 1 << 0;
+
+#[derive(Debug)]
+#[repr(C)]
+pub struct DynamicInfo {
+    pub magic: usize,
+    pub version: usize,
+    pub next_addr: usize,
+    pub next_mode: usize,
+    pub options: usize,
+    pub boot_hart: usize,
+}
+
+impl crate::AsBinary for DynamicInfo {}
+
+impl DynamicInfo {
+    pub const DEFAULT: Self = Self {
+        magic: MAGIC,
+        version: VERSION,
+        next_addr: 0,
+        next_mode: NEXT_MODE_S,
+        options: 0,
+        boot_hart: 0,
+    };
+
+    /// 从 `addr` 读取信息结构。
+    ///
+    /// 前一阶段不一定遵守 `fw_dynamic` 约定，所以只接受位于 SRAM 或 DRAM 中、对齐且魔数正确的结构。
+    #[inline]
+    pub fn from_addr(addr: usize) -> Option<&'static Self> {
+        const SRAM_LEN: usize = 32 << 10;
+        const DRAM_LEN: usize = 1 << 30;
+        if addr % core::mem::align_of::<Self>() != 0
+            || !((SRAM..SRAM + SRAM_LEN).contains(&addr) || (DRAM..DRAM + DRAM_LEN).contains(&addr))
+        {
+            return None;
+        }
+        let info = unsafe { &*(addr as *const Self) };
+        if info.magic == MAGIC {
+            Some(info)
+        } else {
+            None
+        }
+    }
+}
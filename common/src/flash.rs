@@ -9,6 +9,8 @@ pub struct Meta {
     see: MetaEntry,
     kernel: MetaEntry,
     dtb: MetaEntry,
+    kernel_load: u32,
+    kernel_entry: u32,
 }
 
 #[derive(Debug)]
@@ -46,12 +48,31 @@ impl Meta {
         see: MetaEntry::DEFAULT,
         kernel: MetaEntry::DEFAULT,
         dtb: MetaEntry::DEFAULT,
+        kernel_load: !0,
+        kernel_entry: !0,
     };
 
     read_payload!(see);
     read_payload!(kernel);
     read_payload!(dtb);
 
+    /// 内核在 DRAM 中的加载地址和入口地址。
+    ///
+    /// 未设置加载地址时使用 [`crate::memory::KERNEL`]，未设置入口地址时从加载地址开始执行。
+    #[inline]
+    pub fn kernel_addr(&self) -> (usize, usize) {
+        // 0 和 0xffffffff 认为是无效值
+        let load = match self.kernel_load {
+            0 | 0xffff_ffff => crate::memory::KERNEL,
+            addr => addr as usize,
+        };
+        let entry = match self.kernel_entry {
+            0 | 0xffff_ffff => load,
+            addr => addr as usize,
+        };
+        (load, entry)
+    }
+
     #[inline]
     pub fn set_see(&mut self, base: u32, size: u32) {
         self.see = MetaEntry {
@@ -68,6 +89,12 @@ impl Meta {
         };
     }
 
+    #[inline]
+    pub fn set_kernel_addr(&mut self, load: Option<u32>, entry: Option<u32>) {
+        self.kernel_load = load.unwrap_or(!0);
+        self.kernel_entry = entry.unwrap_or(!0);
+    }
+
     #[inline]
     pub fn set_dtb(&mut self, base: u32, size: u32) {
         self.dtb = MetaEntry {
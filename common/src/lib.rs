@@ -1,6 +1,7 @@
 #![no_std]
 
 mod arrow;
+pub mod dynamic;
 pub mod flash;
 pub mod memory;
 
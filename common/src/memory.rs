@@ -2,6 +2,8 @@
 pub const DRAM: usize = 0x4000_0000;
 pub const KERNEL: usize = 0x4020_0000;
 pub const META: usize = 0x0002_0068;
+/// 没有设备树时假定的 DRAM 大小。
+pub const DRAM_SIZE_DEFAULT: usize = 512 << 20;
 
 #[inline]
 pub fn dtb_offset(mem_size: usize) -> u32 {
@@ -16,6 +18,7 @@ pub struct Meta {
     pub see: u32,
     pub kernel: u32,
     pub dtb: u32,
+    pub kernel_entry: u32,
 }
 
 const NONE: u32 = !0;
@@ -41,6 +44,7 @@ impl Meta {
         see: NONE,
         kernel: NONE,
         dtb: NONE,
+        kernel_entry: NONE,
     };
 
     #[inline]
@@ -62,6 +66,17 @@ impl Meta {
     read_payload!(kernel);
     read_payload!(dtb);
 
+    /// 内核入口，未设置时从内核加载地址开始执行。
+    ///
+    /// 旧版 SPL 的元数据没有这个字段，此处是链接时对齐填充的 0，同样视为未设置。
+    #[inline]
+    pub const fn kernel_entry(&self) -> Option<usize> {
+        match self.kernel_entry {
+            NONE | 0 => self.kernel(),
+            offset => Some(DRAM + offset as usize),
+        }
+    }
+
     #[inline]
     pub fn set_see(&mut self, val: u32) {
         self.see = val;
@@ -72,6 +87,11 @@ impl Meta {
         self.kernel = val;
     }
 
+    #[inline]
+    pub fn set_kernel_entry(&mut self, val: u32) {
+        self.kernel_entry = val;
+    }
+
     #[inline]
     pub fn set_dtb(&mut self, val: u32) {
         self.dtb = val;
@@ -18,6 +18,9 @@ SECTIONS {
         *(.text.entry)
         . = ALIGN(4);
         *(.text.trap_handler)
+        . = ALIGN(4);
+        *(.text.suspend)
+        esuspend = .;
         *(.text .text.*)
     } > DDR
     .rodata : ALIGN(8) {
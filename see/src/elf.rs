@@ -0,0 +1,191 @@
+//! 加载 ELF 格式的内核。
+
+use core::ops::Range;
+
+const ELFCLASS64: u8 = 2;
+const ELFDATA2LSB: u8 = 1;
+const EM_RISCV: u16 = 243;
+const PT_LOAD: u32 = 1;
+
+/// 最多支持的程序头数量。
+const MAX_PHNUM: usize = 16;
+
+#[repr(C)]
+struct ElfHeader {
+    e_ident: [u8; 16],
+    _e_type: u16,
+    e_machine: u16,
+    _e_version: u32,
+    e_entry: u64,
+    e_phoff: u64,
+    _e_shoff: u64,
+    _e_flags: u32,
+    _e_ehsize: u16,
+    e_phentsize: u16,
+    e_phnum: u16,
+    _e_shentsize: u16,
+    _e_shnum: u16,
+    _e_shstrndx: u16,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct ProgramHeader {
+    p_type: u32,
+    _p_flags: u32,
+    p_offset: u64,
+    _p_vaddr: u64,
+    p_paddr: u64,
+    p_filesz: u64,
+    p_memsz: u64,
+    _p_align: u64,
+}
+
+impl ProgramHeader {
+    const EMPTY: Self = Self {
+        p_type: 0,
+        _p_flags: 0,
+        p_offset: 0,
+        _p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: 0,
+        p_memsz: 0,
+        _p_align: 0,
+    };
+
+    /// 段在内存中的范围。
+    #[inline]
+    fn dst(&self) -> Option<Range<usize>> {
+        let start = usize::try_from(self.p_paddr).ok()?;
+        let end = start.checked_add(usize::try_from(self.p_memsz).ok()?)?;
+        Some(start..end)
+    }
+
+    /// 段在文件中的数据，位于 `image` 处。
+    #[inline]
+    fn src(&self, image: usize) -> Option<Range<usize>> {
+        let start = image.checked_add(usize::try_from(self.p_offset).ok()?)?;
+        let end = start.checked_add(usize::try_from(self.p_filesz).ok()?)?;
+        Some(start..end)
+    }
+}
+
+/// 加载完成的内核。
+pub(crate) struct Loaded {
+    /// 入口地址。
+    pub entry: usize,
+    /// 最低的加载地址。
+    pub base: usize,
+}
+
+/// 如果 `image` 处是 ELF 文件，把各段加载到各自的物理地址，清零 bss。
+///
+/// 段只能加载到 `valid` 范围内，且不能覆盖 `reserved`；入口必须位于某个段中。
+/// 不是 ELF 文件时返回 `None`，作为裸二进制使用。
+///
+/// 段按程序头的顺序原地搬移，要求各段的目标互不重叠，
+/// 且每段写入的数据不覆盖此后才搬移的段在文件中的数据。
+pub(crate) fn load(image: usize, valid: Range<usize>, reserved: Range<usize>) -> Option<Loaded> {
+    let header = unsafe { (image as *const ElfHeader).read_unaligned() };
+    if header.e_ident[..4] != *b"\x7fELF" {
+        return None;
+    }
+    if header.e_ident[4] != ELFCLASS64
+        || header.e_ident[5] != ELFDATA2LSB
+        || header.e_machine != EM_RISCV
+    {
+        panic!("kernel is not a RV64 little-endian ELF file");
+    }
+    let phnum = header.e_phnum as usize;
+    if header.e_phentsize as usize != core::mem::size_of::<ProgramHeader>() || phnum > MAX_PHNUM {
+        panic!("unsupported ELF program headers");
+    }
+    // 先把程序头拷出来，搬移段时可能覆盖文件头
+    let phdrs = usize::try_from(header.e_phoff)
+        .ok()
+        .and_then(|off| image.checked_add(off))
+        .filter(|start| {
+            start
+                .checked_add(phnum * core::mem::size_of::<ProgramHeader>())
+                .is_some()
+        })
+        .unwrap_or_else(|| panic!("invalid ELF program header offset {:#x}", header.e_phoff));
+    let mut segments = [ProgramHeader::EMPTY; MAX_PHNUM];
+    let mut len = 0;
+    for i in 0..phnum {
+        let ph = unsafe { (phdrs as *const ProgramHeader).add(i).read_unaligned() };
+        if ph.p_type == PT_LOAD && ph.p_memsz > 0 {
+            segments[len] = ph;
+            len += 1;
+        }
+    }
+    if len == 0 {
+        panic!("no loadable segment in ELF file");
+    }
+    let segments = &segments[..len];
+    let entry = header.e_entry as usize;
+    if !segments
+        .iter()
+        .any(|ph| ph.dst().map_or(false, |dst| dst.contains(&entry)))
+    {
+        panic!("ELF entry {entry:#x} is outside loadable segments");
+    }
+    // 检查目标地址
+    for (i, ph) in segments.iter().enumerate() {
+        let dst = match ph.dst() {
+            Some(dst) if ph.p_filesz <= ph.p_memsz && ph.src(image).is_some() => dst,
+            _ => panic!("invalid ELF segment at {:#x}", ph.p_paddr),
+        };
+        // 搬移时写入的范围，bss 在全部搬完后才清零
+        let written = dst.start..dst.start + ph.p_filesz as usize;
+        if dst.start < valid.start
+            || dst.end > valid.end
+            || overlaps(&dst, &reserved)
+            || segments[..i]
+                .iter()
+                .any(|other| overlaps(&dst, &other.dst().unwrap()))
+            || segments[i + 1..].iter().any(|later| {
+                later
+                    .src(image)
+                    .map_or(true, |src| overlaps(&written, &src))
+            })
+        {
+            panic!("cannot load ELF segment to {dst:#x?}");
+        }
+    }
+    // 搬移各段，全部搬完再清零 bss
+    for ph in segments {
+        println!(
+            "[rustsbi] load ELF segment {:#x?} ({:#x} bytes from file)",
+            ph.dst().unwrap(),
+            ph.p_filesz
+        );
+        unsafe {
+            core::ptr::copy(
+                (image + ph.p_offset as usize) as *const u8,
+                ph.p_paddr as *mut u8,
+                ph.p_filesz as _,
+            )
+        };
+    }
+    for ph in segments {
+        unsafe {
+            core::ptr::write_bytes(
+                (ph.p_paddr + ph.p_filesz) as *mut u8,
+                0,
+                (ph.p_memsz - ph.p_filesz) as _,
+            )
+        };
+    }
+    unsafe { core::arch::asm!("fence.i") };
+    Some(Loaded {
+        entry,
+        base: segments.iter().map(|ph| ph.p_paddr as usize).min().unwrap(),
+    })
+}
+
+/// 两个范围是否重叠。
+#[inline]
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
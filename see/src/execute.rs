@@ -102,10 +102,30 @@ impl Context {
     }
 
     fn handle_ecall(&mut self) -> bool {
-        use rustsbi::spec::{binary::*, hsm::*, srst::*};
+        use crate::suspend::{system_suspend, EID_SUSP};
+        use rustsbi::spec::{base::*, binary::*, hsm::*, srst::*};
         let extension = self.a(7);
+        // 遗留 getchar 没有输入时返回 -1，rustsbi 只能返回截断到 u8 的值
+        if extension == rustsbi::spec::legacy::LEGACY_CONSOLE_GETCHAR {
+            *self.a_mut(0) =
+                crate::extensions::LegacyConsole::try_getchar().map_or(usize::MAX, |c| c as _);
+            self.mepc = self.mepc.wrapping_add(4);
+            return true;
+        }
         let function = self.a(6);
-        let ans = rustsbi::ecall(
+        // 系统挂起，成功后从恢复地址继续执行
+        if extension == EID_SUSP {
+            let ans = system_suspend(function, self.a(0));
+            if ans.error == RET_SUCCESS {
+                self.resume(self.a(1), self.a(2));
+            } else {
+                *self.a_mut(0) = ans.error;
+                *self.a_mut(1) = ans.value;
+                self.mepc = self.mepc.wrapping_add(4);
+            }
+            return true;
+        }
+        let mut ans = rustsbi::ecall(
             extension,
             function,
             [
@@ -117,6 +137,10 @@ impl Context {
                 self.a(5),
             ],
         );
+        // rustsbi 不知道 SUSP 扩展
+        if extension == EID_BASE && function == PROBE_EXTENSION && self.a(0) == EID_SUSP {
+            ans.value = 1;
+        }
         // 判断导致退出执行流程的调用
         if ans.error == RET_SUCCESS {
             match extension {
@@ -129,7 +153,8 @@ impl Context {
                             Ok(HART_SUSPEND_TYPE_NON_RETENTIVE)
                         ) =>
                     {
-                        return false;
+                        self.resume(self.a(1), self.a(2));
+                        return true;
                     }
                     _ => {}
                 },
@@ -155,6 +180,16 @@ impl Context {
         true
     }
 
+    /// 从非保留挂起或系统挂起恢复：关中断、关分页，以 a0 = hartid，a1 = opaque 跳转到 `resume_addr`。
+    fn resume(&mut self, resume_addr: usize, opaque: usize) {
+        const SIE: usize = 1 << 1;
+        unsafe { core::arch::asm!("csrw satp, zero") };
+        self.mstatus &= !SIE;
+        self.mepc = resume_addr;
+        *self.a_mut(0) = 0;
+        *self.a_mut(1) = opaque;
+    }
+
     fn emulate_rdtime(&mut self, ins: usize) -> bool {
         const RD_MASK: usize = ((1 << 5) - 1) << 7;
         if ins & !RD_MASK == 0xC0102073 {
@@ -3,23 +3,41 @@ use hal::{
     pac::UART0,
 };
 use riscv::register::mip;
-use rustsbi::{spec::binary::SbiRet, HartMask};
+use rustsbi::{
+    spec::{binary::SbiRet, hsm::*},
+    HartMask,
+};
 
-struct LegacyConsole;
+pub(crate) struct LegacyConsole;
 struct Timer;
 struct Reset;
 struct Ipi;
+struct Hsm;
 
 pub fn init() {
     rustsbi::legacy_stdio::init_legacy_stdio(&LegacyConsole);
     rustsbi::init_timer(&Timer);
     rustsbi::init_reset(&Reset);
     rustsbi::init_ipi(&Ipi);
+    rustsbi::init_hsm(&Hsm);
+}
+
+impl LegacyConsole {
+    /// 从串口接收一个字符，接收 FIFO 为空时立即返回 `None`。
+    pub fn try_getchar() -> Option<u8> {
+        let uart = unsafe { &*UART0::ptr() };
+        if uart.usr.read().rfne().bit_is_set() {
+            Some(uart.rbr().read().rbr().bits())
+        } else {
+            None
+        }
+    }
 }
 
 impl rustsbi::legacy_stdio::LegacyStdio for LegacyConsole {
+    /// 遗留的 getchar 调用在 [`crate::execute`] 中截获，这里只供 rustsbi 内部使用，不阻塞。
     fn getchar(&self) -> u8 {
-        unimplemented!()
+        Self::try_getchar().unwrap_or(!0)
     }
 
     fn putchar(&self, ch: u8) {
@@ -62,3 +80,39 @@ impl rustsbi::Ipi for Ipi {
         SbiRet::ok(0)
     }
 }
+
+impl rustsbi::Hsm for Hsm {
+    fn hart_start(&self, hartid: usize, _start_addr: usize, _opaque: usize) -> SbiRet {
+        // 只有一个核，它总在运行
+        if hartid == 0 {
+            SbiRet::already_available()
+        } else {
+            SbiRet::invalid_param()
+        }
+    }
+
+    fn hart_stop(&self) -> SbiRet {
+        SbiRet::not_supported()
+    }
+
+    fn hart_get_status(&self, hartid: usize) -> SbiRet {
+        if hartid == 0 {
+            SbiRet::ok(HART_STATE_STARTED)
+        } else {
+            SbiRet::invalid_param()
+        }
+    }
+
+    /// 两种默认挂起都只等待中断，非保留挂起的恢复地址由 [`crate::execute`] 设置。
+    ///
+    /// 系统挂起见 [`crate::suspend`]。
+    fn hart_suspend(&self, suspend_type: u32, _resume_addr: usize, _opaque: usize) -> SbiRet {
+        match suspend_type {
+            HART_SUSPEND_TYPE_RETENTIVE | HART_SUSPEND_TYPE_NON_RETENTIVE => {
+                unsafe { riscv::asm::wfi() };
+                SbiRet::ok(0)
+            }
+            _ => SbiRet::invalid_param(),
+        }
+    }
+}
@@ -2,9 +2,11 @@
 #![no_main]
 #![feature(naked_functions, asm_const)]
 
+mod elf;
 mod execute;
 mod extensions;
 mod hart_csr_utils;
+mod suspend;
 
 #[macro_use] // for print
 extern crate rustsbi;
@@ -25,6 +27,9 @@ struct Supervisor {
 /// 2. 设置启动栈
 /// 3. 跳转到 rust 入口函数
 ///
+/// 前一阶段遵守 `fw_dynamic` 约定时，a0 = hartid，a1 = 设备树地址，a2 = 信息结构地址，
+/// 这些寄存器原样传给 rust 入口函数。
+///
 /// # Safety
 ///
 /// 裸函数。
@@ -53,8 +58,8 @@ unsafe extern "C" fn entry() -> ! {
     )
 }
 
-extern "C" fn rust_main() {
-    use common::memory::*;
+extern "C" fn rust_main(_hartid: usize, fdt: usize, info: usize) {
+    use common::{dynamic::*, memory::*};
     use execute::execute_supervisor;
 
     extern "C" {
@@ -65,8 +70,35 @@ extern "C" fn rust_main() {
 
     extensions::init();
 
-    let meta = Meta::static_ref();
-    let board_info = match meta.dtb() {
+    // 优先使用 fw_dynamic 信息，否则使用 SPL 留在 SRAM 的元数据
+    let (kernel, kernel_entry, dtb, options) = match DynamicInfo::from_addr(info) {
+        Some(info) => {
+            if info.version < VERSION_MIN || info.next_mode != NEXT_MODE_S {
+                panic!(
+                    "unsupported fw_dynamic info: version {}, next mode {}",
+                    info.version, info.next_mode
+                );
+            }
+            // fw_dynamic 只给出入口；入口与 SPL 记录的一致时，从元数据取回加载地址
+            let meta = Meta::static_ref();
+            let kernel = match meta.kernel() {
+                Some(kernel) if meta.kernel_entry() == Some(info.next_addr) => kernel,
+                _ => info.next_addr,
+            };
+            (
+                kernel,
+                info.next_addr,
+                Some(fdt).filter(|a| *a != 0),
+                info.options,
+            )
+        }
+        None => {
+            let meta = Meta::static_ref();
+            let kernel = meta.kernel().unwrap_or(0);
+            (kernel, meta.kernel_entry().unwrap_or(kernel), meta.dtb(), 0)
+        }
+    };
+    let board_info = match dtb {
         Some(dtb) => parse_board_info(dtb),
         None => {
             println!("[rustsbi] no dtb file detected");
@@ -74,13 +106,13 @@ extern "C" fn rust_main() {
         }
     };
 
-    let kernel = meta.kernel().unwrap_or(0);
-    print!(
-        "\
+    if options & OPTION_NO_BOOT_PRINTS == 0 {
+        print!(
+            "\
 [rustsbi] RustSBI version {ver_sbi}, adapting to RISC-V SBI v1.0.0
 {logo}
 [rustsbi] Implementation     : RustSBI-D1 Version {ver_impl}
-[rustsbi] Extensions         : [legacy console, timer, reset, ipi]
+[rustsbi] Extensions         : [legacy v0.1, timer, reset, ipi, hsm, susp]
 [rustsbi] Platform Name      : {model}
 [rustsbi] Platform SMP       : 1
 [rustsbi] Platform Memory    : {mem:#x?}
@@ -88,30 +120,48 @@ extern "C" fn rust_main() {
 [rustsbi] Device Tree Region : {dtb:#x?}
 [rustsbi] Firmware Address   : {firmware:#x}
 [rustsbi] Supervisor Address : {kernel:#x}
+[rustsbi] Supervisor Entry   : {kernel_entry:#x}
 ",
-        model = board_info.as_ref().map_or("unknown", |i| i.model.as_str()),
-        mem = board_info.as_ref().map_or(0..0, |i| i.mem.clone()),
-        dtb = board_info.as_ref().map_or(0..0, |i| i.dtb.clone()),
-        ver_sbi = rustsbi::VERSION,
-        logo = rustsbi::logo(),
-        ver_impl = env!("CARGO_PKG_VERSION"),
-        firmware = entry as usize,
-    );
+            model = board_info.as_ref().map_or("unknown", |i| i.model.as_str()),
+            mem = board_info.as_ref().map_or(0..0, |i| i.mem.clone()),
+            dtb = board_info.as_ref().map_or(0..0, |i| i.dtb.clone()),
+            ver_sbi = rustsbi::VERSION,
+            logo = rustsbi::logo(),
+            ver_impl = env!("CARGO_PKG_VERSION"),
+            firmware = entry as usize,
+        );
+    }
 
     if kernel == 0 {
         arrow_walk()
     } else {
-        const DEFAULT: Range<usize> = memory::DRAM..memory::DRAM + (512 << 20);
+        const DEFAULT: Range<usize> = memory::DRAM..memory::DRAM + memory::DRAM_SIZE_DEFAULT;
         let mem = board_info.as_ref().map_or(DEFAULT, |i| i.mem.clone());
-        set_pmp(mem, kernel);
+        // 内核是 ELF 文件时，按程序头加载到各段的物理地址，从 ELF 入口启动
+        let see_end = unsafe { &ebss as *const u64 as usize };
+        let dtb_region = board_info.as_ref().map_or(0..0, |i| i.dtb.clone());
+        let (base, entry) = match elf::load(kernel, see_end..mem.end, dtb_region) {
+            Some(_) if kernel_entry != kernel => {
+                panic!("kernel entry {kernel_entry:#x} is set for an ELF kernel at {kernel:#x}")
+            }
+            Some(elf) => {
+                println!(
+                    "[rustsbi] ELF kernel loaded at {:#x}, entry at {:#x}",
+                    elf.base, elf.entry
+                );
+                (elf.base, elf.entry)
+            }
+            None => (kernel, kernel_entry),
+        };
+        set_pmp(mem, base);
         hart_csr_utils::print_pmps();
 
         hal::plic::allow_supervisor();
 
         let dtb = board_info.as_ref().map_or(0, |i| i.dtb.start);
-        println!("execute_supervisor at {kernel:#x} with a1 = {dtb:#x}");
+        println!("execute_supervisor at {entry:#x} with a1 = {dtb:#x}");
         execute_supervisor(Supervisor {
-            start_addr: kernel,
+            start_addr: entry,
             opaque: dtb,
         })
     }
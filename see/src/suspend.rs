@@ -0,0 +1,191 @@
+//! 系统挂起（SUSP 扩展），只支持挂起到内存（Suspend-to-RAM）。
+//!
+//! rustsbi 还没有这个扩展，所以由 [`crate::execute`] 在转交 rustsbi 之前截获调用和探测。
+//!
+//! DDR 进入自刷新后 DRAM 不可访问，所以挂起流程不能在 SEE 所在的 DRAM 中执行。
+//! 这里先把 [`sram_suspend`] 拷贝到 SRAM，再从 SRAM 调用它；
+//! 它唤醒后恢复 DDR，返回到 DRAM 中保留的调用点继续执行。
+//!
+//! 这是不完整的实现，也还没有在硬件上验证过：
+//!
+//! - 只关闭了 PLL_CPU，PLL_PERI、音视频 PLL 和各总线时钟都保持运行；
+//! - PLL_DDR 和 DRAM 时钟保持运行：关闭它们之后需要重新锁定 PHY/DLL，
+//!   这一步属于 SPL 中的 DRAM 初始化，SEE 无法重做。
+
+use common::memory::SRAM;
+use rustsbi::spec::binary::SbiRet;
+
+/// `"SUSP"`
+pub(crate) const EID_SUSP: usize = 0x5355_5350;
+pub(crate) const SYSTEM_SUSPEND: usize = 0;
+pub(crate) const SUSPEND_TYPE_SUSPEND_TO_RAM: u32 = 0;
+
+/// 挂起代码在 SRAM 中的位置。
+///
+/// 这会覆盖 SPL 的映像，包括 SPL 留给 SEE 的元数据和 fw_dynamic 信息，它们只在 SEE 启动时使用。
+const SRAM_SUSPEND: usize = SRAM + (16 << 10);
+/// SRAM A1 的结尾。
+const SRAM_END: usize = SRAM + (32 << 10);
+
+// CCU 寄存器
+const PLL_CPU_CTRL: usize = 0x0200_1000;
+const RISCV_CLK: usize = 0x0200_1d00;
+
+const PLL_EN: usize = 1 << 31;
+const PLL_LOCK: usize = 1 << 28;
+const PLL_OUTPUT_GATE: usize = 1 << 27;
+const RISCV_CLK_SRC_SEL: usize = 0b111 << 24;
+
+// MCTL 寄存器
+const MCTL_COM: usize = 0x0310_2000;
+const MCTL_PWRCTL: usize = 0x0310_3004;
+const MCTL_STATR: usize = 0x0310_3018;
+
+const STATR_NORMAL: usize = 1;
+const STATR_SELF_REFRESH: usize = 3;
+
+/// 处理 SUSP 扩展的调用。
+///
+/// 返回成功时，调用者应该从 `resume_addr` 恢复执行。
+pub(crate) fn system_suspend(function: usize, sleep_type: usize) -> SbiRet {
+    match function {
+        SYSTEM_SUSPEND => match u32::try_from(sleep_type) {
+            Ok(SUSPEND_TYPE_SUSPEND_TO_RAM) => {
+                suspend_to_ram();
+                SbiRet::ok(0)
+            }
+            _ => SbiRet::invalid_param(),
+        },
+        _ => SbiRet::not_supported(),
+    }
+}
+
+/// 挂起整个系统，直到 `mie` 中使能的任一中断挂起。
+fn suspend_to_ram() {
+    extern "C" {
+        static esuspend: u8;
+    }
+    unsafe {
+        let start = sram_suspend as usize;
+        let len = &esuspend as *const u8 as usize - start;
+        assert!(
+            SRAM_SUSPEND + len <= SRAM_END,
+            "suspend code ({len} bytes) does not fit in SRAM"
+        );
+        (SRAM_SUSPEND as *mut u8).copy_from_nonoverlapping(start as *const u8, len);
+        core::arch::asm!("fence.i");
+        let sram_suspend: unsafe extern "C" fn() = core::mem::transmute(SRAM_SUSPEND);
+        sram_suspend();
+    }
+}
+
+/// 在 SRAM 中执行的挂起流程。
+///
+/// 1. 写回数据缓存，保存并关闭 DRAM 主设备访问
+/// 2. DDR 进入自刷新
+/// 3. CPU 切换到 24 MHz 晶振，关闭 PLL_CPU
+/// 4. `wfi` 等待唤醒
+/// 5. 按相反顺序恢复
+///
+/// # Safety
+///
+/// 裸函数，只能拷贝到 SRAM 后调用：只使用立即数地址和相对跳转，不访问栈和 DRAM。
+#[naked]
+#[link_section = ".text.suspend"]
+unsafe extern "C" fn sram_suspend() {
+    core::arch::asm!(
+        // 写回数据缓存（th.dcache.call; th.sync）
+        "   .word 0x0010000b
+            .word 0x0180000b
+        ",
+        // 关闭 DRAM 主设备访问
+        // a4, a5, a6: MAER0, MAER1, MAER2
+        "   li   t0, {mctl_com}
+            lw   a4, 0x20(t0)
+            lw   a5, 0x24(t0)
+            lw   a6, 0x28(t0)
+            li   t1, 1
+            sw   t1, 0x20(t0)
+            sw   zero, 0x24(t0)
+            sw   zero, 0x28(t0)
+        ",
+        // DDR 进入自刷新
+        "   li   t0, {pwrctl}
+            lw   t1, 0(t0)
+            ori  t1, t1, 1
+            sw   t1, 0(t0)
+            li   t0, {statr}
+            li   t2, {self_refresh}
+        1:  lw   t1, 0(t0)
+            andi t1, t1, 7
+            bne  t1, t2, 1b
+        ",
+        // CPU 切换到晶振，关闭 PLL_CPU
+        // a2: RISCV_CLK, a3: PLL_CPU_CTRL
+        "   li   t3, {pll_en_gate}
+            not  t3, t3
+
+            li   t0, {riscv_clk}
+            lw   a2, 0(t0)
+            li   t1, {clk_src}
+            not  t1, t1
+            and  t1, a2, t1
+            sw   t1, 0(t0)
+
+            li   t0, {pll_cpu}
+            lw   a3, 0(t0)
+            and  t1, a3, t3
+            sw   t1, 0(t0)
+        ",
+        // 等待唤醒
+        "   wfi
+        ",
+        // 恢复 PLL_CPU，锁定后切回 CPU 时钟
+        "   li   t3, {pll_gate}
+            not  t3, t3
+            li   t4, {pll_lock}
+
+            li   t0, {pll_cpu}
+            and  t1, a3, t3
+            sw   t1, 0(t0)
+        2:  lw   t1, 0(t0)
+            and  t1, t1, t4
+            beqz t1, 2b
+            sw   a3, 0(t0)
+
+            li   t0, {riscv_clk}
+            sw   a2, 0(t0)
+        ",
+        // DDR 退出自刷新
+        "   li   t0, {pwrctl}
+            lw   t1, 0(t0)
+            andi t1, t1, -2
+            sw   t1, 0(t0)
+            li   t0, {statr}
+            li   t2, {normal}
+        4:  lw   t1, 0(t0)
+            andi t1, t1, 7
+            bne  t1, t2, 4b
+        ",
+        // 恢复 DRAM 主设备访问，返回 DRAM
+        "   li   t0, {mctl_com}
+            sw   a4, 0x20(t0)
+            sw   a5, 0x24(t0)
+            sw   a6, 0x28(t0)
+            fence
+            ret
+        ",
+        mctl_com     = const MCTL_COM,
+        pwrctl       = const MCTL_PWRCTL,
+        statr        = const MCTL_STATR,
+        self_refresh = const STATR_SELF_REFRESH,
+        normal       = const STATR_NORMAL,
+        pll_cpu      = const PLL_CPU_CTRL,
+        pll_en_gate  = const PLL_EN | PLL_OUTPUT_GATE,
+        pll_gate     = const PLL_OUTPUT_GATE,
+        pll_lock     = const PLL_LOCK,
+        riscv_clk    = const RISCV_CLK,
+        clk_src      = const RISCV_CLK_SRC_SEL,
+        options(noreturn)
+    )
+}
@@ -5,21 +5,39 @@ mod consts {
     pub(super) const CMD_READ_ID: u8 = 0x9f;
     pub(super) const CMD_READ_PAGE: u8 = 0x13;
     pub(super) const CMD_READ_CACHE: u8 = 0x03;
+    pub(super) const CMD_READ_CACHE_SEQ: u8 = 0x31;
+    pub(super) const CMD_READ_CACHE_END: u8 = 0x3f;
     pub(super) const FEAT_STATUS: u8 = 0xc0;
     pub(super) const LEN_PAGE_BITS: u32 = 11;
     pub(super) const LEN_PAGE: u32 = 1 << LEN_PAGE_BITS;
     pub(super) const LEN_PAGE_MASK: u32 = LEN_PAGE - 1;
+    pub(super) const LEN_BLOCK_BITS: u32 = LEN_PAGE_BITS + 6; // 64 页
+    pub(super) const LEN_BLOCK: u32 = 1 << LEN_BLOCK_BITS;
+    pub(super) const LEN_BLOCK_MASK: u32 = LEN_BLOCK - 1;
+    /// 已确认支持 READ PAGE CACHE SEQUENTIAL（31h/3Fh）的厂商 ID。
+    pub(super) const SEQ_READ_MIDS: [u8; 1] = [
+        0x2c, // Micron
+    ];
 }
 
 use consts::*;
 
 /// NAND Flash with SPI.
-pub struct SpiNand<SPI: Instance, PINS>(Spi<SPI, PINS>);
+pub struct SpiNand<SPI: Instance, PINS> {
+    spi: Spi<SPI, PINS>,
+    /// 是否使用连续缓存读。
+    seq_read: bool,
+}
 
 impl<SPI: Instance, PINS> SpiNand<SPI, PINS> {
     #[inline]
     pub fn new(inner: Spi<SPI, PINS>) -> Self {
-        Self(inner)
+        let mut ans = Self {
+            spi: inner,
+            seq_read: false,
+        };
+        ans.seq_read = SEQ_READ_MIDS.contains(&ans.read_id()[0]);
+        ans
     }
 }
 
@@ -29,29 +47,90 @@ impl<SPI: Instance, PINS> SpiNand<SPI, PINS> {
     pub fn read_id(&self) -> [u8; 3] {
         let mut buf = [0u8; 3];
         self.wait();
-        self.0.transfer([CMD_READ_ID], 1, &mut buf);
+        self.spi.transfer([CMD_READ_ID], 1, &mut buf);
         buf
     }
 
     /// Copies bytes from `base` address to `buf`.
+    ///
+    /// 已知支持的 flash 使用连续缓存读，否则逐页读。
     #[inline]
     pub fn copy_into(&mut self, mut base: u32, mut buf: &mut [u8]) {
+        if !self.seq_read {
+            self.copy_by_page(base, buf);
+            return;
+        }
+        // 有些 flash 的连续读在块边界停止，所以按块分段
+        while !buf.is_empty() {
+            let len = buf.len().min((LEN_BLOCK - (base & LEN_BLOCK_MASK)) as _);
+            let (head, tail) = buf.split_at_mut(len);
+            self.copy_sequential(base, head);
+            base += len as u32;
+            buf = tail;
+        }
+    }
+
+    /// 逐页读，每页重新发送页地址。
+    fn copy_by_page(&mut self, mut base: u32, mut buf: &mut [u8]) {
         while !buf.is_empty() {
             let mut cmd = u32::to_be_bytes(base >> LEN_PAGE_BITS);
             cmd[0] = CMD_READ_PAGE;
             self.wait();
-            self.0.transfer(cmd, 0, []);
+            self.spi.transfer(cmd, 0, []);
 
             let ca = base & LEN_PAGE_MASK;
             let (head, tail) = buf.split_at_mut(buf.len().min((LEN_PAGE - ca) as _));
             base += head.len() as u32;
             buf = tail;
 
-            let mut cmd = u32::to_be_bytes(ca);
-            cmd[1] = CMD_READ_CACHE;
-            self.wait();
-            self.0.transfer(&cmd[1..], 1, head);
+            self.read_cache(ca, head);
+        }
+    }
+
+    /// 块内连续缓存读（READ PAGE CACHE SEQUENTIAL），
+    /// 在主机读取当前页的同时，由 flash 在后台把下一页读入数据寄存器。
+    fn copy_sequential(&mut self, base: u32, mut buf: &mut [u8]) {
+        if buf.is_empty() {
+            return;
         }
+        let first = base >> LEN_PAGE_BITS;
+        let last = (base + buf.len() as u32 - 1) >> LEN_PAGE_BITS;
+        let mut ca = base & LEN_PAGE_MASK;
+        // 第一页读入缓存
+        let mut cmd = u32::to_be_bytes(first);
+        cmd[0] = CMD_READ_PAGE;
+        self.wait();
+        self.spi.transfer(cmd, 0, []);
+        for page in first..=last {
+            // 只有一页时直接读缓存，否则先把当前页转入缓存，并启动下一页的读取
+            if first != last {
+                self.wait();
+                self.spi.transfer(
+                    [if page == last {
+                        CMD_READ_CACHE_END
+                    } else {
+                        CMD_READ_CACHE_SEQ
+                    }],
+                    0,
+                    [],
+                );
+            }
+
+            let (head, tail) = buf.split_at_mut(buf.len().min((LEN_PAGE - ca) as _));
+            buf = tail;
+
+            self.read_cache(ca, head);
+            ca = 0;
+        }
+    }
+
+    /// 从缓存的 `ca` 列开始读出 `buf`。
+    #[inline]
+    fn read_cache(&self, ca: u32, buf: &mut [u8]) {
+        let mut cmd = u32::to_be_bytes(ca);
+        cmd[1] = CMD_READ_CACHE;
+        self.wait();
+        self.spi.transfer(&cmd[1..], 1, buf);
     }
 }
 
@@ -60,7 +139,7 @@ impl<SPI: Instance, PINS> SpiNand<SPI, PINS> {
     fn get_feature(&self, key: u8) -> u8 {
         let mut feature = 0u8;
 
-        self.0.transfer(
+        self.spi.transfer(
             [CMD_GET_FEATURE, key],
             0,
             core::slice::from_mut(&mut feature),
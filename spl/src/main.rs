@@ -7,8 +7,9 @@ mod logging;
 mod magic;
 
 use common::{
+    dynamic::DynamicInfo,
     flash::{Meta as FlashMeta, META as META_POS},
-    memory::{dtb_offset, parse_memory_size, Meta as MemMeta, DRAM, KERNEL},
+    memory::{dtb_offset, parse_memory_size, Meta as MemMeta, DRAM, DRAM_SIZE_DEFAULT, KERNEL},
     AsBinary, EgonHead,
 };
 use core::{arch::asm, panic::PanicInfo};
@@ -41,6 +42,12 @@ unsafe extern "C" fn main_jump() -> ! {
 #[link_section = ".head.meta"]
 static mut META: MemMeta = MemMeta::DEFAULT;
 
+/// 按 `fw_dynamic` 约定传给下一阶段的信息。
+static mut DYNAMIC_INFO: DynamicInfo = DynamicInfo::DEFAULT;
+
+/// 传给下一阶段的设备树地址。
+static mut FDT: usize = 0;
+
 /// Jump over head data to executable code.
 ///
 /// # Safety
@@ -87,9 +94,15 @@ unsafe extern "C" fn start() -> ! {
         // 换回头 128 字节
         "   call {swap}",
         // 启动！
+        // a0: hartid, a1: 设备树地址, a2: fw_dynamic 信息
         "   call {main}
             fence.i
-            jr   a0
+            mv   t0, a0
+            csrr a0, mhartid
+            la   t1, {fdt}
+            ld   a1, 0(t1)
+            la   a2, {info}
+            jr   t0
         ",
         head       =   sym head_jump,
         swap       =   sym head_swap,
@@ -99,6 +112,8 @@ unsafe extern "C" fn start() -> ! {
         stack      =   sym STACK,
         stack_size = const STACK_SIZE,
         main       =   sym main,
+        fdt        =   sym FDT,
+        info       =   sym DYNAMIC_INFO,
         options(noreturn)
     )
 }
@@ -151,7 +166,7 @@ extern "C" fn main() -> usize {
         if meta.see == !0 {
             arrow_walk()
         } else {
-            return DRAM + meta.see as usize;
+            return handoff(&meta, DRAM + meta.see as usize);
         }
     } else {
         let _ = Out << "boot from brom" << Endl;
@@ -191,13 +206,18 @@ extern "C" fn main() -> usize {
     };
 
     // 拷贝 dtb
+    let mut dtb = 0..0;
+    let mut dram_end = DRAM + DRAM_SIZE_DEFAULT;
     if let Some((pos, len)) = meta.dtb() {
         let _ = log_loading("dtb", pos, len);
         flash.copy_into(pos, unsafe { static_buf(DRAM, len) });
-        let offset = dtb_offset(parse_memory_size(DRAM as _));
+        let mem_size = parse_memory_size(DRAM as _);
+        let offset = dtb_offset(mem_size);
+        dram_end = DRAM + mem_size.min(1 << 30);
         unsafe { META.dtb = offset };
         let dst = (DRAM as u32 + offset) as *mut u8;
         unsafe { dst.copy_from_nonoverlapping(DRAM as *const u8, len) };
+        dtb = dst as usize..dst as usize + len;
     }
     // 拷贝 see
     let _ = log_loading("see", see_pos, see_len);
@@ -205,13 +225,41 @@ extern "C" fn main() -> usize {
     unsafe { META.see = 0 };
     // 拷贝 kernel
     if let Some((pos, len)) = meta.kernel() {
-        let _ = log_loading("kernel", pos, len);
-        flash.copy_into(pos, unsafe { static_buf(KERNEL, len) });
-        unsafe { META.kernel = (KERNEL - DRAM) as _ };
+        let (load, entry) = meta.kernel_addr();
+        let _ = log_loading("kernel", pos, len)
+            << "kernel at "
+            << Hex::Fmt(load)
+            << ", entry at "
+            << Hex::Fmt(entry)
+            << Endl;
+        // 内核不能覆盖 see 和 dtb，不能超出 DRAM，入口必须在内核中
+        let end = load + len;
+        if load < KERNEL.max(DRAM + see_len)
+            || end > dram_end
+            || !(load..end).contains(&entry)
+            || (load < dtb.end && dtb.start < end)
+        {
+            let _ = Out << "invalid kernel address" << Endl;
+            arrow_walk()
+        }
+        flash.copy_into(pos, unsafe { static_buf(load, len) });
+        unsafe {
+            META.kernel = (load - DRAM) as _;
+            META.kernel_entry = (entry - DRAM) as _;
+        }
     }
     // 跳转
     let _ = Out << "everyting is ready, jump to main stage at " << Hex::Fmt(DRAM) << Endl << Endl;
-    DRAM
+    handoff(unsafe { &META }, DRAM)
+}
+
+/// 按 `fw_dynamic` 约定填写下一阶段的参数，返回下一阶段入口。
+fn handoff(meta: &MemMeta, entry: usize) -> usize {
+    unsafe {
+        DYNAMIC_INFO.next_addr = meta.kernel_entry().unwrap_or(0);
+        FDT = meta.dtb().unwrap_or(0);
+    }
+    entry
 }
 
 const LOGO: &str = r"
@@ -5,7 +5,7 @@ use std::{
     ffi::OsStr,
     fs::{self, File},
     io::{Error as IoError, ErrorKind as IoErrorKind, Read, Seek, SeekFrom},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 #[derive(Args)]
@@ -16,6 +16,12 @@ pub(crate) struct Components {
     see: bool,
     #[clap(long, global = true)]
     kernel: Option<PathBuf>,
+    /// kernel load address in DRAM, default 0x40200000
+    #[clap(long, global = true, value_parser = parse_addr)]
+    kernel_load: Option<u32>,
+    /// kernel entry address, default to load address
+    #[clap(long, global = true, value_parser = parse_addr)]
+    kernel_entry: Option<u32>,
     #[clap(long, global = true)]
     dt: Option<PathBuf>,
 }
@@ -83,6 +89,55 @@ impl Components {
         Ok(ans)
     }
 
+    /// 检查内核加载地址和入口地址，没有内核时返回 `None`。
+    fn kernel_addr(&self, target: &Target) -> Result<Option<(usize, usize)>, XError> {
+        use common::memory::{dtb_offset, DRAM, DRAM_SIZE_DEFAULT, KERNEL};
+        let kernel = match target.kernel.as_deref() {
+            Some(kernel) => kernel,
+            None if self.kernel_load.is_none() && self.kernel_entry.is_none() => return Ok(None),
+            None => {
+                return Err(XError::InvalidProcedure(
+                    "kernel load and entry address are given without kernel".into(),
+                ))
+            }
+        };
+        let load = self.kernel_load.map_or(KERNEL, |a| a as usize);
+        let entry = self.kernel_entry.map_or(load, |a| a as usize);
+        // 加载地址以下是 see
+        if load < KERNEL {
+            return Err(XError::InvalidProcedure(format!(
+                "kernel load address {load:#x} overlaps see, it should be at least {KERNEL:#x}"
+            )));
+        }
+        // ELF 内核从文件中的入口启动
+        let mut magic = [0u8; 4];
+        if self.kernel_entry.is_some()
+            && File::open(kernel)?.read(&mut magic)? == magic.len()
+            && magic == *b"\x7fELF"
+        {
+            return Err(XError::InvalidProcedure(
+                "kernel entry cannot be set for an ELF kernel".into(),
+            ));
+        }
+        let end = load + kernel.metadata()?.len() as usize;
+        // 内核不能超出 DRAM，也不能覆盖 spl 放置的 dtb
+        let dram_end = match target.dtb.as_deref() {
+            Some(dtb) => DRAM + dtb_offset(memory_size(dtb)?) as usize,
+            None => DRAM + DRAM_SIZE_DEFAULT,
+        };
+        if end > dram_end {
+            return Err(XError::InvalidProcedure(format!(
+                "kernel {load:#x}..{end:#x} exceeds available memory, which ends at {dram_end:#x}"
+            )));
+        }
+        if !(load..end).contains(&entry) {
+            return Err(XError::InvalidProcedure(format!(
+                "kernel entry {entry:#x} is outside the kernel {load:#x}..{end:#x}"
+            )));
+        }
+        Ok(Some((load, entry)))
+    }
+
     pub fn asm(&self, arg: AsmArg) -> Result<(), XError> {
         let mut packages = vec![];
         if self.spl {
@@ -148,6 +203,7 @@ impl Components {
         }
         // 生成
         let target = self.make()?;
+        let kernel_addr = self.kernel_addr(&target)?;
         let mut meta = Meta::DEFAULT;
         // 写入 see
         if let Some(see) = &target.see {
@@ -156,21 +212,15 @@ impl Components {
             info!("write {} to {DRAM:#x}", see.display());
             Xfel::write(DRAM, see).invoke();
             // 写入 kernel
-            if let Some(kernel) = &target.kernel {
-                meta.set_kernel((KERNEL - DRAM) as _);
-                info!("write {} to {KERNEL:#x}", kernel.display());
-                Xfel::write(KERNEL, kernel).invoke();
+            if let (Some(kernel), Some((load, entry))) = (&target.kernel, kernel_addr) {
+                meta.set_kernel((load - DRAM) as _);
+                meta.set_kernel_entry((entry - DRAM) as _);
+                info!("write {} to {load:#x}", kernel.display());
+                Xfel::write(load, kernel).invoke();
             }
             // 写入 dtb
             if let Some(dtb) = &target.dtb {
-                let len = dtb.metadata().unwrap().len() as usize;
-
-                let mut file: File = File::open(dtb)?;
-                let mut buf = vec![0u32; (len + 3) / 4];
-                file.read_exact(unsafe {
-                    std::slice::from_raw_parts_mut(buf.as_mut_ptr().cast(), len)
-                })?;
-                let offset = dtb_offset(parse_memory_size(buf.as_ptr().cast()));
+                let offset = dtb_offset(memory_size(dtb)?);
                 let address = DRAM + offset as usize;
                 meta.set_dtb(offset);
                 info!("write {} to {address:#x}", dtb.display());
@@ -201,6 +251,7 @@ impl Components {
         use common::{flash::*, memory, AsBinary};
 
         let target = self.make()?;
+        let kernel_addr = self.kernel_addr(&target)?;
 
         if let Some(spl) = target.spl {
             use common::EgonHead;
@@ -235,8 +286,9 @@ impl Components {
             meta.set_see(SEE, see.metadata().unwrap().len() as _);
             Xfel::spinand_write(SEE as _, see).invoke();
         }
-        if let Some(kernel) = target.kernel {
+        if let (Some(kernel), Some((load, entry))) = (target.kernel, kernel_addr) {
             meta.set_kernel(KERNEL, kernel.metadata().unwrap().len() as _);
+            meta.set_kernel_addr(Some(load as _), Some(entry as _));
             Xfel::spinand_write(KERNEL as _, kernel).invoke();
         }
         if let Some(dtb) = target.dtb {
@@ -253,3 +305,22 @@ impl Components {
         Ok(())
     }
 }
+
+/// 从设备树文件读取内存大小。
+fn memory_size(dtb: &Path) -> Result<usize, XError> {
+    use common::memory::parse_memory_size;
+    let len = dtb.metadata()?.len() as usize;
+    // 设备树要求 4 字节对齐
+    let mut buf = vec![0u32; (len + 3) / 4];
+    File::open(dtb)?
+        .read_exact(unsafe { std::slice::from_raw_parts_mut(buf.as_mut_ptr().cast(), len) })?;
+    Ok(parse_memory_size(buf.as_ptr().cast()))
+}
+
+/// 解析地址，支持 `0x` 前缀的十六进制。
+fn parse_addr(s: &str) -> Result<u32, std::num::ParseIntError> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+}